@@ -1,5 +1,20 @@
 use clap::{Parser, Subcommand};
 
+/// Validate a `--scheme` flag value. Only `eip191` and `eip712` are
+/// implemented signing schemes (see `crate::signing`); anything else would
+/// get written verbatim into `signing.scheme` while the snapshot actually
+/// gets signed eip191 (`commands::seal::run` only special-cases `"eip712"`),
+/// leaving a snapshot whose recorded scheme lies about how it was signed.
+fn parse_scheme(value: &str) -> Result<String, String> {
+    match value {
+        "eip191" | "eip712" => Ok(value.to_string()),
+        other => Err(format!(
+            "unknown signing scheme '{}' (expected eip191 or eip712)",
+            other
+        )),
+    }
+}
+
 #[derive(Parser)]
 #[command(name = "swarmhive")]
 #[command(about = "SwarmHive sovereign compute CLI", long_about = None)]
@@ -7,6 +22,10 @@ use clap::{Parser, Subcommand};
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Shell out to the `ipfs` CLI instead of the Kubo HTTP API
+    #[arg(long, global = true)]
+    pub ipfs_cli: bool,
 }
 
 #[derive(Subcommand)]
@@ -34,6 +53,10 @@ pub struct InitCmd {
     /// Pool to join
     #[arg(long, default_value = "swarmpool.eth")]
     pub pool: String,
+
+    /// Signing scheme for the GENESIS_MINER snapshot (eip191 or eip712)
+    #[arg(long, default_value = "eip191", value_parser = parse_scheme)]
+    pub scheme: String,
 }
 
 #[derive(Parser)]
@@ -42,9 +65,17 @@ pub struct WatchCmd {
     #[arg(long, default_value = "swarmpool.eth")]
     pub pool: String,
 
-    /// Sync interval in seconds
+    /// Sync interval in seconds (poll mode only)
     #[arg(long, default_value_t = 10)]
     pub interval: u64,
+
+    /// Pubsub topic override (defaults to swarm/<pool>/jobs)
+    #[arg(long)]
+    pub topic: Option<String>,
+
+    /// Use fixed-interval polling instead of pubsub push
+    #[arg(long)]
+    pub poll: bool,
 }
 
 #[derive(Parser)]
@@ -61,6 +92,10 @@ pub struct ClaimCmd {
     /// Lease duration in seconds
     #[arg(long, default_value_t = 900)]
     pub lease: u64,
+
+    /// Signing scheme for the CLAIM snapshot (eip191 or eip712)
+    #[arg(long, default_value = "eip191", value_parser = parse_scheme)]
+    pub scheme: String,
 }
 
 #[derive(Parser)]
@@ -75,6 +110,10 @@ pub struct ProveCmd {
     /// Compute time in seconds
     #[arg(long)]
     pub compute_seconds: u64,
+
+    /// Signing scheme for the PROOF snapshot (eip191 or eip712)
+    #[arg(long, default_value = "eip191", value_parser = parse_scheme)]
+    pub scheme: String,
 }
 
 #[derive(Parser)]
@@ -82,4 +121,16 @@ pub struct SealCmd {
     /// Epoch number to seal
     #[arg(long)]
     pub epoch: u64,
+
+    /// Signing scheme for the EPOCH_SEAL snapshot (eip191 or eip712)
+    #[arg(long, default_value = "eip191", value_parser = parse_scheme)]
+    pub scheme: String,
+
+    /// Anchor the merkle root and seal CID to the chain in `chain` config
+    #[arg(long)]
+    pub anchor: bool,
+
+    /// With --anchor, preview the transaction instead of broadcasting it
+    #[arg(long)]
+    pub dry_run: bool,
 }
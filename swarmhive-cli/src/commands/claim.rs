@@ -1,14 +1,13 @@
 //! swarmhive claim - Claim a SOLO job
 
 use crate::cli::ClaimCmd;
-use crate::config;
+use crate::config::Config;
+use crate::ipfs::IpfsClient;
 use anyhow::Result;
 use chrono::Utc;
 use serde_json::json;
 
-pub async fn run(cmd: ClaimCmd) -> Result<()> {
-    let config = config::load()?;
-
+pub async fn run(cmd: ClaimCmd, config: &Config, _ipfs: &IpfsClient) -> Result<()> {
     println!("Claiming job: {}", cmd.job_id);
     println!("Miner: {}", config.identity.ens);
     println!("Lease: {}s", cmd.lease);
@@ -29,7 +28,7 @@ pub async fn run(cmd: ClaimCmd) -> Result<()> {
             "lease_seconds": cmd.lease
         },
         "signing": {
-            "scheme": "eip191",
+            "scheme": cmd.scheme,
             "did": format!("ens:{}", config.identity.ens),
             "payload_hash": "",
             "signature": ""
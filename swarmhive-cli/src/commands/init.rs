@@ -1,20 +1,21 @@
 //! swarmhive init - Register as a compute provider
 
 use crate::cli::InitCmd;
-use crate::config::{self, Config, IdentityConfig, IpfsConfig, PoolConfig};
+use crate::config::{self, Config, IdentityConfig, IpfsConfig, PinningConfig, PoolConfig};
+use crate::ipfs::IpfsClient;
 use crate::signing;
 use anyhow::Result;
 use chrono::Utc;
 use serde_json::json;
 
-pub async fn run(cmd: InitCmd) -> Result<()> {
+pub async fn run(cmd: InitCmd, ipfs: &IpfsClient) -> Result<()> {
     println!("Initializing SwarmHive miner...");
     println!("ENS: {}", cmd.ens);
     println!("Pool: {}", cmd.pool);
     println!();
 
     // 1. Check IPFS daemon
-    if !crate::ipfs::is_daemon_running() {
+    if !ipfs.is_daemon_running().await {
         println!("Warning: IPFS daemon not running. Publishing will fail.");
     }
 
@@ -53,7 +54,7 @@ pub async fn run(cmd: InitCmd) -> Result<()> {
             }
         },
         "signing": {
-            "scheme": "eip191",
+            "scheme": cmd.scheme,
             "did": format!("ens:{}", cmd.ens),
             "payload_hash": "",
             "signature": ""
@@ -70,7 +71,7 @@ pub async fn run(cmd: InitCmd) -> Result<()> {
 
     // 6. Publish to IPFS (stub)
     println!("Publishing to IPFS... (stub)");
-    // let cid = crate::ipfs::add(&serde_json::to_string_pretty(&snapshot)?)?;
+    // let cid = ipfs.add(&serde_json::to_string_pretty(&snapshot)?).await?;
     // println!("Genesis CID: {}", cid);
 
     // 7. Write config
@@ -86,7 +87,9 @@ pub async fn run(cmd: InitCmd) -> Result<()> {
         ipfs: IpfsConfig {
             api: "/ip4/127.0.0.1/tcp/5001".to_string(),
             gateway: "https://ipfs.io/ipfs".to_string(),
+            pinning: PinningConfig::default(),
         },
+        chain: None,
     };
     config::save(&config)?;
 
@@ -1,14 +1,13 @@
 //! swarmhive prove - Submit proof of compute
 
 use crate::cli::ProveCmd;
-use crate::config;
+use crate::config::Config;
+use crate::ipfs::IpfsClient;
 use anyhow::Result;
 use chrono::Utc;
 use serde_json::json;
 
-pub async fn run(cmd: ProveCmd) -> Result<()> {
-    let config = config::load()?;
-
+pub async fn run(cmd: ProveCmd, config: &Config, _ipfs: &IpfsClient) -> Result<()> {
     println!("Proving job: {}", cmd.job_id);
     println!("Miner: {}", config.identity.ens);
     println!("Result: {}", cmd.result);
@@ -32,7 +31,7 @@ pub async fn run(cmd: ProveCmd) -> Result<()> {
             }
         },
         "signing": {
-            "scheme": "eip191",
+            "scheme": cmd.scheme,
             "did": format!("ens:{}", config.identity.ens),
             "payload_hash": "",
             "signature": ""
@@ -1,32 +1,327 @@
 //! swarmhive seal - Seal an epoch (controller only)
 
 use crate::cli::SealCmd;
-use anyhow::Result;
+use crate::config::{self, Config};
+use crate::ipfs::IpfsClient;
+use crate::merkle::MerkleTree;
+use crate::payout;
+use crate::settlement::{AnchorOutcome, Settlement};
+use crate::signing;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use ethers::types::Address;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+pub async fn run(cmd: SealCmd, config: &Config, ipfs: &IpfsClient) -> Result<()> {
+    // TODO: verify caller is controller (merlin.swarmos.eth)
 
-pub async fn run(cmd: SealCmd) -> Result<()> {
     println!("Sealing epoch: {}", cmd.epoch);
     println!();
 
-    // TODO:
-    // 1. Verify caller is controller (merlin.swarmos.eth)
-    // 2. Fetch all proofs for epoch
-    // 3. Aggregate job summaries
-    // 4. Calculate payouts (75% miners / 25% hive)
-    // 5. Build EPOCH_SEAL snapshot
-    // 6. Sign with controller key
-    // 7. Publish to IPFS under /epochs/{N}/seal.json
-    // 8. Update epoch.json status to SEALED
+    let (pool_cid, proofs) = fetch_proofs(ipfs, config, cmd.epoch).await?;
+    let unique = dedupe_verified(ipfs, &pool_cid, proofs).await;
+
+    let job_count = unique
+        .iter()
+        .filter_map(|(_, p)| p.pointer("/body/job_id").and_then(|v| v.as_str()))
+        .collect::<HashSet<_>>()
+        .len();
+
+    // The bee-23 JOB schema doesn't carry a price yet, so volume is
+    // measured in compute-seconds until job pricing lands.
+    let mut compute_by_miner: BTreeMap<String, u64> = BTreeMap::new();
+    for (_, proof) in &unique {
+        let miner = proof
+            .pointer("/body/miner")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let compute_seconds = proof
+            .pointer("/body/compute_seconds")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+        *compute_by_miner.entry(miner).or_insert(0) += compute_seconds;
+    }
+    let total_volume: u128 = compute_by_miner.values().map(|&s| s as u128).sum();
+
+    let mut leaves: Vec<(String, [u8; 32], Value)> = unique
+        .into_iter()
+        .map(|(cid, proof)| {
+            let canonical = signing::canonical_json(&proof)?;
+            let leaf = ethers::utils::keccak256(canonical.as_bytes());
+            Ok((cid, leaf, proof))
+        })
+        .collect::<Result<_>>()?;
+    leaves.sort_by(|a, b| a.1.cmp(&b.1));
+
+    let tree = MerkleTree::build(leaves.iter().map(|(_, leaf, _)| *leaf).collect());
+    let proof_cids: Vec<&str> = leaves.iter().map(|(cid, _, _)| cid.as_str()).collect();
+    let inclusion_proofs: Vec<Value> = leaves
+        .iter()
+        .enumerate()
+        .map(|(i, (cid, _, proof))| {
+            let path: Vec<String> = tree.proof(i).iter().map(hex::encode).collect();
+            json!({
+                "proof_cid": cid,
+                "job_id": proof.pointer("/body/job_id"),
+                "miner": proof.pointer("/body/miner"),
+                "path": path,
+            })
+        })
+        .collect();
+
+    let (hive_amount, miner_payouts) = payout::compute_payouts(total_volume, &compute_by_miner);
 
     println!("=== Epoch {} Seal ===", cmd.epoch);
-    println!("Jobs: (stub)");
-    println!("Proofs: (stub)");
-    println!("Volume: (stub)");
+    println!("Jobs: {}", job_count);
+    println!("Proofs: {}", leaves.len());
+    println!("Volume: {}", total_volume);
     println!();
     println!("Payouts:");
-    println!("  (stub)");
+    println!("  hive: {}", hive_amount);
+    for p in &miner_payouts {
+        println!("  {}: {} ({}s)", p.miner, p.amount, p.compute_seconds);
+    }
     println!();
-    println!("Merkle root: (stub)");
-    println!("Seal CID: (stub)");
 
+    let ts = Utc::now().timestamp();
+    let mut seal = json!({
+        "type": "EPOCH_SEAL",
+        "version": "bee-23@1.0",
+        "id": format!("epoch-seal-{}-{}", cmd.epoch, ts),
+        "ts": ts,
+        "issuer": config.identity.ens,
+        "pool": config.pool.name,
+        "body": {
+            "epoch": cmd.epoch,
+            "job_count": job_count,
+            "proof_count": leaves.len(),
+            "total_volume": total_volume.to_string(),
+            "merkle_root": format!("0x{}", hex::encode(tree.root)),
+            "proof_cids": proof_cids,
+            "payouts": {
+                "hive": hive_amount.to_string(),
+                "miners": miner_payouts.iter().map(|p| json!({
+                    "miner": p.miner,
+                    "compute_seconds": p.compute_seconds,
+                    "amount": p.amount.to_string(),
+                })).collect::<Vec<_>>(),
+            },
+        },
+        "signing": {
+            "scheme": cmd.scheme.clone(),
+            "did": format!("ens:{}", config.identity.ens),
+            "payload_hash": "",
+            "signature": ""
+        }
+    });
+
+    let key_hex = std::fs::read_to_string(&config.identity.key_path)
+        .context("failed to read controller key_path")?;
+    let wallet = signing::load_wallet(key_hex.trim())?;
+
+    let hash = signing::payload_hash(&seal)?;
+    seal["signing"]["payload_hash"] = json!(hash);
+    let signature = if cmd.scheme == "eip712" {
+        let domain = signing::Eip712Domain::new(
+            config.chain.as_ref().map(|c| c.chain_id).unwrap_or(1),
+            config
+                .chain
+                .as_ref()
+                .and_then(|c| c.contract_address.parse::<ethers::types::Address>().ok())
+                .unwrap_or_default(),
+        );
+        seal["signing"]["domain"] = signing::eip712_domain_json(&domain);
+        signing::sign_eip712(&seal, &domain, &wallet).await?
+    } else {
+        signing::sign_eip191(&hash, &wallet).await?
+    };
+    seal["signing"]["signature"] = json!(signature);
+
+    let seal_cid = ipfs.add(&serde_json::to_string_pretty(&seal)?).await?;
+    println!("Merkle root: 0x{}", hex::encode(tree.root));
+    println!("Seal CID: {}", seal_cid);
+
+    for (i, inclusion) in inclusion_proofs.into_iter().enumerate() {
+        let miner = leaves[i]
+            .2
+            .pointer("/body/miner")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown");
+        let cid = ipfs
+            .add(&serde_json::to_string_pretty(&inclusion)?)
+            .await?;
+        println!("Inclusion proof ({}): {}", miner, cid);
+    }
+
+    write_epoch_status(cmd.epoch, &seal_cid, &format!("0x{}", hex::encode(tree.root)))?;
+
+    if cmd.anchor {
+        let chain = config
+            .chain
+            .as_ref()
+            .context("swarmhive seal --anchor requires a [chain] section in config.toml")?;
+        let settlement = Settlement::new(chain, wallet).await?;
+
+        println!();
+        match settlement
+            .anchor_seal(cmd.epoch, tree.root, &seal_cid, cmd.dry_run)
+            .await?
+        {
+            AnchorOutcome::Preview {
+                to,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                calldata,
+            } => {
+                println!("=== Anchor Preview (dry-run) ===");
+                println!("Contract: {:?}", to);
+                println!("Max fee/gas: {} wei", max_fee_per_gas);
+                println!("Max priority fee/gas: {} wei", max_priority_fee_per_gas);
+                println!("Calldata: 0x{}", hex::encode(calldata));
+            }
+            AnchorOutcome::Sent(receipt) => {
+                println!("=== Anchored On-Chain ===");
+                println!("Tx hash: {:?}", receipt.transaction_hash);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Fetch every proof snapshot published for `epoch`. Resolves the pool's
+/// mutable pointer once, then lists and cats each proof, returning the
+/// resolved pool CID alongside so callers can look up other pool-relative
+/// paths (e.g. claims) without resolving again. An unresolvable pool
+/// (nothing published yet) is treated as an empty epoch.
+async fn fetch_proofs(
+    ipfs: &IpfsClient,
+    config: &Config,
+    epoch: u64,
+) -> Result<(String, Vec<(String, Value)>)> {
+    let pool_cid = match ipfs.resolve(&config.pool.name).await {
+        Ok(cid) => cid,
+        Err(_) => return Ok((String::new(), vec![])),
+    };
+    let proofs_dir = format!("{}/proofs/epoch-{}", pool_cid.trim_start_matches("/ipfs/"), epoch);
+
+    let entries = ipfs.ls_entries(&proofs_dir).await.unwrap_or_default();
+    let mut proofs = Vec::with_capacity(entries.len());
+    for (name, cid) in entries {
+        let content = ipfs
+            .cat(&cid)
+            .await
+            .with_context(|| format!("failed to fetch proof {}", name))?;
+        let proof: Value = serde_json::from_str(&content)
+            .with_context(|| format!("invalid proof snapshot: {}", name))?;
+        proofs.push((cid, proof));
+    }
+    Ok((pool_cid, proofs))
+}
+
+/// Verify each proof's signature, check the signer is actually the miner it
+/// claims to be, and drop duplicate `(job_id, miner)` submissions, keeping
+/// the first one seen.
+///
+/// A valid signature alone only proves *someone's* key signed the proof —
+/// not that the signer is `body.miner`. Anyone can sign a PROOF naming a
+/// different miner's ENS with an inflated `compute_seconds` and redirect
+/// that share of the pool. The CLAIM for the same `job_id` was signed by
+/// whoever actually claimed it (see `commands::claim`), so requiring the
+/// PROOF's recovered signer to match the CLAIM's recovered signer binds the
+/// proof to the miner that legitimately holds the job; a proof with no
+/// matching claim, or a signer that doesn't match it, is dropped.
+async fn dedupe_verified(
+    ipfs: &IpfsClient,
+    pool_cid: &str,
+    proofs: Vec<(String, Value)>,
+) -> Vec<(String, Value)> {
+    let mut seen = HashSet::new();
+    let mut unique = Vec::new();
+    let mut claim_signers: HashMap<String, Option<Address>> = HashMap::new();
+
+    for (cid, proof) in proofs {
+        let Ok(signer) = signing::recover_signer(&proof) else {
+            continue;
+        };
+        let job_id = proof
+            .pointer("/body/job_id")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let miner = proof
+            .pointer("/body/miner")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+        if job_id.is_empty() {
+            continue;
+        }
+
+        let claim_signer = match claim_signers.get(&job_id) {
+            Some(signer) => *signer,
+            None => {
+                let resolved = fetch_claim_signer(ipfs, pool_cid, &job_id).await;
+                claim_signers.insert(job_id.clone(), resolved);
+                resolved
+            }
+        };
+
+        if claim_signer != Some(signer) {
+            eprintln!(
+                "Warning: dropping proof {} for job {}: signer does not match the job's claimant",
+                cid, job_id
+            );
+            continue;
+        }
+
+        if seen.insert((job_id, miner)) {
+            unique.push((cid, proof));
+        }
+    }
+
+    unique
+}
+
+/// Recover the signer of the CLAIM snapshot for `job_id`, published under
+/// `/claims/{job_id}/` (see `commands::claim`). `None` if no claim is found
+/// or none of its entries verify, so a PROOF without a matching CLAIM is
+/// rejected rather than trusted.
+async fn fetch_claim_signer(ipfs: &IpfsClient, pool_cid: &str, job_id: &str) -> Option<Address> {
+    let dir = format!(
+        "{}/claims/{}",
+        pool_cid.trim_start_matches("/ipfs/"),
+        job_id
+    );
+    let entries = ipfs.ls_entries(&dir).await.ok()?;
+    for (_, cid) in entries {
+        let Ok(content) = ipfs.cat(&cid).await else {
+            continue;
+        };
+        let Ok(claim) = serde_json::from_str::<Value>(&content) else {
+            continue;
+        };
+        if let Ok(signer) = signing::recover_signer(&claim) {
+            return Some(signer);
+        }
+    }
+    None
+}
+
+/// Persist epoch status locally under `~/.swarmhive/epochs/{N}.json`.
+/// Publishing a mutable pool-wide `epoch.json` needs IPNS support the
+/// client doesn't have yet.
+fn write_epoch_status(epoch: u64, seal_cid: &str, merkle_root: &str) -> Result<()> {
+    let path = config::config_dir().join("epochs").join(format!("{}.json", epoch));
+    std::fs::create_dir_all(path.parent().expect("epochs dir has a parent"))?;
+    let status = json!({
+        "epoch": epoch,
+        "status": "SEALED",
+        "seal_cid": seal_cid,
+        "merkle_root": merkle_root,
+    });
+    std::fs::write(&path, serde_json::to_string_pretty(&status)?)?;
     Ok(())
 }
@@ -1,9 +1,11 @@
 //! swarmhive submit - Submit a job snapshot
 
 use crate::cli::SubmitCmd;
+use crate::config::Config;
+use crate::ipfs::IpfsClient;
 use anyhow::Result;
 
-pub async fn run(cmd: SubmitCmd) -> Result<()> {
+pub async fn run(cmd: SubmitCmd, _config: &Config, _ipfs: &IpfsClient) -> Result<()> {
     println!("Submitting job: {}", cmd.file);
 
     // TODO:
@@ -1,16 +1,111 @@
 //! swarmhive watch - Observe the memepool
 
 use crate::cli::WatchCmd;
+use crate::config::Config;
+use crate::ipfs::IpfsClient;
+use crate::signing;
 use anyhow::Result;
 use std::time::Duration;
 
-pub async fn run(cmd: WatchCmd) -> Result<()> {
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+pub async fn run(cmd: WatchCmd, _config: &Config, ipfs: &IpfsClient) -> Result<()> {
     println!("Watching pool: {}", cmd.pool);
-    println!("Interval: {}s", cmd.interval);
     println!();
     println!("Press Ctrl+C to exit.");
     println!();
 
+    if cmd.poll {
+        return poll_loop(&cmd, ipfs).await;
+    }
+
+    let topic = cmd
+        .topic
+        .clone()
+        .unwrap_or_else(|| format!("swarm/{}/jobs", cmd.pool));
+
+    match ipfs.pubsub_sub(&topic).await {
+        Ok(subscription) => {
+            println!("Subscribed to pubsub topic: {}", topic);
+            println!();
+            pubsub_loop(&cmd, ipfs, &topic, subscription).await
+        }
+        Err(err) => {
+            println!("Pubsub unavailable ({}); falling back to polling.", err);
+            println!();
+            poll_loop(&cmd, ipfs).await
+        }
+    }
+}
+
+/// Subscribe-and-react loop: reacts to job announcements as they arrive,
+/// re-subscribing with exponential backoff if the stream drops.
+async fn pubsub_loop(
+    cmd: &WatchCmd,
+    ipfs: &IpfsClient,
+    topic: &str,
+    mut subscription: crate::ipfs::PubsubSubscription,
+) -> Result<()> {
+    let mut backoff = MIN_BACKOFF;
+
+    loop {
+        match subscription.next().await {
+            Ok(Some(job)) => {
+                backoff = MIN_BACKOFF;
+                print_job(&job);
+            }
+            Ok(None) => {
+                let reason = "Pubsub subscription closed".to_string();
+                match reconnect(ipfs, topic, &reason, &mut backoff).await {
+                    Some(s) => subscription = s,
+                    None => return poll_loop(cmd, ipfs).await,
+                }
+            }
+            Err(err) => {
+                let reason = format!("Pubsub read error: {}", err);
+                match reconnect(ipfs, topic, &reason, &mut backoff).await {
+                    Some(s) => subscription = s,
+                    None => return poll_loop(cmd, ipfs).await,
+                }
+            }
+        }
+    }
+}
+
+/// Sleep for `backoff` (doubling it up to `MAX_BACKOFF`), then try to
+/// re-subscribe to `topic`. Returns `None` if re-subscribing fails, meaning
+/// the caller should fall back to `poll_loop`.
+async fn reconnect(
+    ipfs: &IpfsClient,
+    topic: &str,
+    reason: &str,
+    backoff: &mut Duration,
+) -> Option<crate::ipfs::PubsubSubscription> {
+    println!(
+        "[{}] {}; reconnecting in {}s...",
+        chrono::Utc::now().format("%H:%M:%S"),
+        reason,
+        backoff.as_secs()
+    );
+    tokio::time::sleep(*backoff).await;
+    *backoff = (*backoff * 2).min(MAX_BACKOFF);
+
+    match ipfs.pubsub_sub(topic).await {
+        Ok(s) => Some(s),
+        Err(err) => {
+            println!("Re-subscribe failed ({}); falling back to polling.", err);
+            None
+        }
+    }
+}
+
+/// Fixed-interval polling loop, used when pubsub is unavailable or `--poll`
+/// was passed.
+async fn poll_loop(cmd: &WatchCmd, _ipfs: &IpfsClient) -> Result<()> {
+    println!("Polling every {}s.", cmd.interval);
+    println!();
+
     loop {
         // TODO:
         // 1. Resolve pool ENS to CID
@@ -23,3 +118,14 @@ pub async fn run(cmd: WatchCmd) -> Result<()> {
         tokio::time::sleep(Duration::from_secs(cmd.interval)).await;
     }
 }
+
+fn print_job(job: &serde_json::Value) {
+    let ts = chrono::Utc::now().format("%H:%M:%S");
+    let id = job.get("id").and_then(|v| v.as_str()).unwrap_or("?");
+    let job_type = job.get("type").and_then(|v| v.as_str()).unwrap_or("?");
+
+    match signing::recover_signer(job) {
+        Ok(signer) => println!("[{}] {} {} (signer: {:?})", ts, job_type, id, signer),
+        Err(err) => println!("[{}] {} {} (unverified: {})", ts, job_type, id, err),
+    }
+}
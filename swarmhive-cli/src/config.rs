@@ -12,6 +12,9 @@ pub struct Config {
     pub identity: IdentityConfig,
     pub pool: PoolConfig,
     pub ipfs: IpfsConfig,
+    /// On-chain anchoring target (optional; controllers only)
+    #[serde(default)]
+    pub chain: Option<ChainConfig>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -30,6 +33,33 @@ pub struct PoolConfig {
 pub struct IpfsConfig {
     pub api: String,
     pub gateway: String,
+    /// Remote pinning service. Unset by default — `gateway` above is a read
+    /// gateway, not a pinning API, and pinning to a third party the user
+    /// hasn't explicitly configured (with credentials) would fail silently
+    /// on every `add`.
+    #[serde(default)]
+    pub pinning: PinningConfig,
+}
+
+/// Remote pinning service config, separate from `IpfsConfig.gateway` because
+/// a pinning API is a different (usually authenticated) endpoint than a read
+/// gateway.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PinningConfig {
+    /// Kubo-compatible pin endpoint, e.g. `https://pin.example.com`.
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// Bearer token sent with each pin request, if the service requires one.
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+/// Ethereum (or L2) target for anchoring epoch seals on-chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainConfig {
+    pub rpc_url: String,
+    pub chain_id: u64,
+    pub contract_address: String,
 }
 
 impl Default for Config {
@@ -46,7 +76,9 @@ impl Default for Config {
             ipfs: IpfsConfig {
                 api: "/ip4/127.0.0.1/tcp/5001".to_string(),
                 gateway: "https://ipfs.io/ipfs".to_string(),
+                pinning: PinningConfig::default(),
             },
+            chain: None,
         }
     }
 }
@@ -1,12 +1,352 @@
-//! IPFS add / cat helpers
+//! IPFS client
 //!
-//! Uses local IPFS daemon via CLI or HTTP API.
+//! Talks to a local Kubo daemon over its HTTP API (`/api/v0/*`), sharing a
+//! single `reqwest::Client` across every command so `seal` fetching hundreds
+//! of proofs in one epoch doesn't pay for a fresh connection per call. Falls
+//! back to shelling out to the `ipfs` CLI (`--ipfs-cli`) for environments
+//! without the HTTP API.
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use base64::Engine as _;
+use reqwest::multipart;
 use std::process::Command;
 
-/// Add content to IPFS and return the CID
-pub fn add(content: &str) -> Result<String> {
+/// A configured remote pinning target, distinct from the read gateway.
+#[derive(Clone)]
+struct PinTarget {
+    endpoint: String,
+    token: Option<String>,
+}
+
+/// Shared IPFS client, constructed once in `main` and passed through the
+/// command handlers.
+#[derive(Clone)]
+pub struct IpfsClient {
+    http: reqwest::Client,
+    api_base: String,
+    /// Remote pinning service (`IpfsConfig.pinning`). `None` unless the user
+    /// explicitly configured one — there's no sane default pinning endpoint,
+    /// and a read gateway is not a pinning API.
+    pin: Option<PinTarget>,
+    /// Shell out to the `ipfs` CLI instead of hitting the HTTP API.
+    cli_fallback: bool,
+}
+
+impl IpfsClient {
+    /// Build a client targeting the Kubo HTTP API described by `api` (a
+    /// multiaddr like `/ip4/127.0.0.1/tcp/5001`). Remote pinning only
+    /// happens if `pinning_endpoint` is set (`IpfsConfig.pinning.endpoint`).
+    pub fn new(
+        api: &str,
+        pinning_endpoint: Option<&str>,
+        pinning_token: Option<&str>,
+        cli_fallback: bool,
+    ) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            api_base: multiaddr_to_http(api),
+            pin: pinning_endpoint
+                .filter(|e| !e.is_empty())
+                .map(|endpoint| PinTarget {
+                    endpoint: endpoint.trim_end_matches('/').to_string(),
+                    token: pinning_token.map(String::from),
+                }),
+            cli_fallback,
+        }
+    }
+
+    /// Add content to IPFS and return the CID.
+    pub async fn add(&self, content: &str) -> Result<String> {
+        if self.cli_fallback {
+            return cli_add(content);
+        }
+
+        let form = multipart::Form::new().part("file", multipart::Part::text(content.to_string()));
+        let resp = self
+            .http
+            .post(format!("{}/api/v0/add?cid-version=1", self.api_base))
+            .multipart(form)
+            .send()
+            .await
+            .context("ipfs add request failed")?;
+        let cid = parse_add_response(resp).await?;
+
+        self.pin_remote(&cid).await;
+        Ok(format!("ipfs://{}", cid))
+    }
+
+    /// Add a file to IPFS.
+    pub async fn add_file(&self, path: &str) -> Result<String> {
+        if self.cli_fallback {
+            return cli_add_file(path);
+        }
+
+        let bytes = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("failed to read {}", path))?;
+        let file_name = path.rsplit('/').next().unwrap_or(path).to_string();
+        let form = multipart::Form::new().part("file", multipart::Part::bytes(bytes).file_name(file_name));
+
+        let resp = self
+            .http
+            .post(format!("{}/api/v0/add?cid-version=1", self.api_base))
+            .multipart(form)
+            .send()
+            .await
+            .context("ipfs add request failed")?;
+        let cid = parse_add_response(resp).await?;
+
+        self.pin_remote(&cid).await;
+        Ok(format!("ipfs://{}", cid))
+    }
+
+    /// Fetch content from IPFS by CID.
+    pub async fn cat(&self, cid: &str) -> Result<String> {
+        if self.cli_fallback {
+            return cli_cat(cid);
+        }
+
+        let cid = cid.strip_prefix("ipfs://").unwrap_or(cid);
+        let resp = self
+            .http
+            .post(format!("{}/api/v0/cat?arg={}", self.api_base, cid))
+            .send()
+            .await
+            .context("ipfs cat request failed")?
+            .error_for_status()
+            .context("ipfs cat failed")?;
+
+        Ok(resp.text().await?)
+    }
+
+    /// Resolve an IPNS or DNSLink name to a CID.
+    pub async fn resolve(&self, name: &str) -> Result<String> {
+        if self.cli_fallback {
+            return cli_resolve(name);
+        }
+
+        let resp: serde_json::Value = self
+            .http
+            .post(format!("{}/api/v0/name/resolve?arg={}", self.api_base, name))
+            .send()
+            .await
+            .context("ipfs resolve request failed")?
+            .error_for_status()
+            .context("ipfs resolve failed")?
+            .json()
+            .await
+            .context("failed to parse ipfs resolve response")?;
+
+        resp.get("Path")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .context("ipfs resolve response missing Path")
+    }
+
+    /// List directory contents at CID.
+    pub async fn ls(&self, cid: &str) -> Result<Vec<String>> {
+        if self.cli_fallback {
+            return cli_ls(cid);
+        }
+
+        let cid = cid.strip_prefix("ipfs://").unwrap_or(cid);
+        let resp: serde_json::Value = self
+            .http
+            .post(format!("{}/api/v0/ls?arg={}", self.api_base, cid))
+            .send()
+            .await
+            .context("ipfs ls request failed")?
+            .error_for_status()
+            .context("ipfs ls failed")?
+            .json()
+            .await
+            .context("failed to parse ipfs ls response")?;
+
+        let names = resp
+            .get("Objects")
+            .and_then(|o| o.as_array())
+            .and_then(|objs| objs.first())
+            .and_then(|obj| obj.get("Links"))
+            .and_then(|l| l.as_array())
+            .map(|links| {
+                links
+                    .iter()
+                    .filter_map(|link| link.get("Name").and_then(|n| n.as_str()))
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(names)
+    }
+
+    /// List directory contents at CID, keeping each entry's own CID
+    /// alongside its name (`ls` only keeps the name, matching the old CLI
+    /// output format). Always uses the HTTP API; `--ipfs-cli` has no effect
+    /// here.
+    pub async fn ls_entries(&self, cid: &str) -> Result<Vec<(String, String)>> {
+        let cid = cid.strip_prefix("ipfs://").unwrap_or(cid);
+        let resp: serde_json::Value = self
+            .http
+            .post(format!("{}/api/v0/ls?arg={}", self.api_base, cid))
+            .send()
+            .await
+            .context("ipfs ls request failed")?
+            .error_for_status()
+            .context("ipfs ls failed")?
+            .json()
+            .await
+            .context("failed to parse ipfs ls response")?;
+
+        let entries = resp
+            .get("Objects")
+            .and_then(|o| o.as_array())
+            .and_then(|objs| objs.first())
+            .and_then(|obj| obj.get("Links"))
+            .and_then(|l| l.as_array())
+            .map(|links| {
+                links
+                    .iter()
+                    .filter_map(|link| {
+                        let name = link.get("Name")?.as_str()?.to_string();
+                        let hash = link.get("Hash")?.as_str()?.to_string();
+                        Some((name, hash))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(entries)
+    }
+
+    /// Subscribe to a pubsub topic, streaming newline-delimited JSON frames
+    /// from `/api/v0/pubsub/sub`. Requires the HTTP API (no CLI fallback, as
+    /// `ipfs pubsub sub` isn't a one-shot command).
+    pub async fn pubsub_sub(&self, topic: &str) -> Result<PubsubSubscription> {
+        if self.cli_fallback {
+            bail!("pubsub subscriptions require the HTTP API; rerun without --ipfs-cli");
+        }
+
+        let resp = self
+            .http
+            .post(format!("{}/api/v0/pubsub/sub?arg={}", self.api_base, topic))
+            .send()
+            .await
+            .context("ipfs pubsub sub request failed")?
+            .error_for_status()
+            .context("ipfs pubsub sub failed")?;
+
+        Ok(PubsubSubscription {
+            resp,
+            buf: Vec::new(),
+        })
+    }
+
+    /// Check if the IPFS daemon is reachable.
+    pub async fn is_daemon_running(&self) -> bool {
+        if self.cli_fallback {
+            return Command::new("ipfs")
+                .args(["id"])
+                .output()
+                .map(|o| o.status.success())
+                .unwrap_or(false);
+        }
+
+        self.http
+            .post(format!("{}/api/v0/id", self.api_base))
+            .send()
+            .await
+            .map(|resp| resp.status().is_success())
+            .unwrap_or(false)
+    }
+
+    /// Best-effort pin to the configured remote pinning service. A no-op
+    /// unless `IpfsConfig.pinning.endpoint` is set. Failures are non-fatal
+    /// since the content is already on the local daemon.
+    async fn pin_remote(&self, cid: &str) {
+        let Some(pin) = &self.pin else {
+            return;
+        };
+
+        let mut req = self
+            .http
+            .post(format!("{}/api/v0/pin/add?arg={}", pin.endpoint, cid));
+        if let Some(token) = &pin.token {
+            req = req.bearer_auth(token);
+        }
+
+        if let Err(err) = req.send().await {
+            eprintln!("Warning: remote pin to {} failed: {}", pin.endpoint, err);
+        }
+    }
+}
+
+/// A live `/api/v0/pubsub/sub` stream. Each message is a Kubo pubsub frame
+/// (`{"from":..,"data":<base64>,"seqno":..,"topicIDs":[..]}`); `next`
+/// base64-decodes `data` and parses it as the announced job JSON.
+pub struct PubsubSubscription {
+    resp: reqwest::Response,
+    buf: Vec<u8>,
+}
+
+impl PubsubSubscription {
+    /// Read the next announced job, or `None` if the subscription closed.
+    pub async fn next(&mut self) -> Result<Option<serde_json::Value>> {
+        loop {
+            if let Some(pos) = self.buf.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = self.buf.drain(..=pos).collect();
+                let line = &line[..line.len() - 1];
+                if line.is_empty() {
+                    continue;
+                }
+
+                let frame: serde_json::Value =
+                    serde_json::from_slice(line).context("invalid pubsub frame")?;
+                let data_b64 = frame
+                    .get("data")
+                    .or_else(|| frame.get("Data"))
+                    .and_then(|v| v.as_str())
+                    .context("pubsub frame missing data")?;
+                let decoded = base64::engine::general_purpose::STANDARD
+                    .decode(data_b64)
+                    .context("pubsub frame data is not valid base64")?;
+                let job: serde_json::Value =
+                    serde_json::from_slice(&decoded).context("pubsub message is not valid JSON")?;
+                return Ok(Some(job));
+            }
+
+            match self.resp.chunk().await.context("pubsub stream read failed")? {
+                Some(chunk) => self.buf.extend_from_slice(&chunk),
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+async fn parse_add_response(resp: reqwest::Response) -> Result<String> {
+    let resp: serde_json::Value = resp
+        .error_for_status()
+        .context("ipfs add failed")?
+        .json()
+        .await
+        .context("failed to parse ipfs add response")?;
+
+    resp.get("Hash")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .context("ipfs add response missing Hash")
+}
+
+/// Convert a multiaddr (`/ip4/127.0.0.1/tcp/5001`) to an HTTP base URL.
+fn multiaddr_to_http(api: &str) -> String {
+    let parts: Vec<&str> = api.split('/').filter(|p| !p.is_empty()).collect();
+    match parts.as_slice() {
+        [_proto, host, _tcp, port] => format!("http://{}:{}", host, port),
+        _ => "http://127.0.0.1:5001".to_string(),
+    }
+}
+
+fn cli_add(content: &str) -> Result<String> {
     let output = Command::new("ipfs")
         .args(["add", "-Q", "--cid-version=1", "-"])
         .stdin(std::process::Stdio::piped())
@@ -19,15 +359,11 @@ pub fn add(content: &str) -> Result<String> {
         anyhow::bail!("ipfs add failed: {}", String::from_utf8_lossy(&output.stderr));
     }
 
-    let cid = String::from_utf8(output.stdout)?
-        .trim()
-        .to_string();
-
+    let cid = String::from_utf8(output.stdout)?.trim().to_string();
     Ok(format!("ipfs://{}", cid))
 }
 
-/// Add a file to IPFS
-pub fn add_file(path: &str) -> Result<String> {
+fn cli_add_file(path: &str) -> Result<String> {
     let output = Command::new("ipfs")
         .args(["add", "-Q", "--cid-version=1", path])
         .output()
@@ -37,15 +373,11 @@ pub fn add_file(path: &str) -> Result<String> {
         anyhow::bail!("ipfs add failed: {}", String::from_utf8_lossy(&output.stderr));
     }
 
-    let cid = String::from_utf8(output.stdout)?
-        .trim()
-        .to_string();
-
+    let cid = String::from_utf8(output.stdout)?.trim().to_string();
     Ok(format!("ipfs://{}", cid))
 }
 
-/// Fetch content from IPFS by CID
-pub fn cat(cid: &str) -> Result<String> {
+fn cli_cat(cid: &str) -> Result<String> {
     let cid = cid.strip_prefix("ipfs://").unwrap_or(cid);
 
     let output = Command::new("ipfs")
@@ -60,17 +392,7 @@ pub fn cat(cid: &str) -> Result<String> {
     Ok(String::from_utf8(output.stdout)?)
 }
 
-/// Check if IPFS daemon is running
-pub fn is_daemon_running() -> bool {
-    Command::new("ipfs")
-        .args(["id"])
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
-}
-
-/// Resolve an IPNS or DNSLink name to a CID
-pub fn resolve(name: &str) -> Result<String> {
+fn cli_resolve(name: &str) -> Result<String> {
     let output = Command::new("ipfs")
         .args(["resolve", "-r", name])
         .output()
@@ -83,8 +405,7 @@ pub fn resolve(name: &str) -> Result<String> {
     Ok(String::from_utf8(output.stdout)?.trim().to_string())
 }
 
-/// List directory contents at CID
-pub fn ls(cid: &str) -> Result<Vec<String>> {
+fn cli_ls(cid: &str) -> Result<Vec<String>> {
     let cid = cid.strip_prefix("ipfs://").unwrap_or(cid);
 
     let output = Command::new("ipfs")
@@ -2,21 +2,44 @@ mod cli;
 mod commands;
 mod config;
 mod ipfs;
+mod merkle;
+mod payout;
+mod settlement;
 mod signing;
 
 use cli::Cli;
 use clap::Parser;
+use ipfs::IpfsClient;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
+    // `init` creates/overwrites the config, so it shouldn't be blocked by
+    // an unreadable or stale one — every other command genuinely needs it.
+    let config = match config::load() {
+        Ok(config) => config,
+        Err(err) if matches!(&cli.command, cli::Commands::Init(_)) => {
+            eprintln!("Warning: ignoring unreadable config ({}); init will overwrite it.", err);
+            config::Config::default()
+        }
+        Err(err) => return Err(err),
+    };
+    // Built once here and shared across every command rather than spinning
+    // up a fresh HTTP client per call (`--ipfs-cli` still runs one `ipfs`
+    // process per call).
+    let ipfs = IpfsClient::new(
+        &config.ipfs.api,
+        config.ipfs.pinning.endpoint.as_deref(),
+        config.ipfs.pinning.token.as_deref(),
+        cli.ipfs_cli,
+    );
 
     match cli.command {
-        cli::Commands::Init(cmd) => commands::init::run(cmd).await,
-        cli::Commands::Watch(cmd) => commands::watch::run(cmd).await,
-        cli::Commands::Submit(cmd) => commands::submit::run(cmd).await,
-        cli::Commands::Claim(cmd) => commands::claim::run(cmd).await,
-        cli::Commands::Prove(cmd) => commands::prove::run(cmd).await,
-        cli::Commands::Seal(cmd) => commands::seal::run(cmd).await,
+        cli::Commands::Init(cmd) => commands::init::run(cmd, &ipfs).await,
+        cli::Commands::Watch(cmd) => commands::watch::run(cmd, &config, &ipfs).await,
+        cli::Commands::Submit(cmd) => commands::submit::run(cmd, &config, &ipfs).await,
+        cli::Commands::Claim(cmd) => commands::claim::run(cmd, &config, &ipfs).await,
+        cli::Commands::Prove(cmd) => commands::prove::run(cmd, &config, &ipfs).await,
+        cli::Commands::Seal(cmd) => commands::seal::run(cmd, &config, &ipfs).await,
     }
 }
@@ -0,0 +1,130 @@
+//! OpenZeppelin-style order-independent Merkle tree
+//!
+//! Leaves are combined pairwise with `keccak256(sorted(left, right))` so the
+//! same pair hashes identically regardless of traversal direction, which is
+//! what lets an `EPOCH_SEAL` inclusion path be verified on-chain with a
+//! single `require(computedRoot == root)` check. Callers are expected to
+//! sort leaves lexicographically before building the tree (`seal::run` does
+//! this over `keccak256(canonical_json(proof))`).
+
+use ethers::utils::keccak256;
+
+/// A built tree: every level from leaves to root, kept around so inclusion
+/// paths can be produced after the fact.
+pub struct MerkleTree {
+    pub root: [u8; 32],
+    layers: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    /// Build a tree over `leaves`, assumed to already be in the desired
+    /// (sorted) order. An empty epoch yields a zero root.
+    pub fn build(leaves: Vec<[u8; 32]>) -> Self {
+        if leaves.is_empty() {
+            return Self {
+                root: [0u8; 32],
+                layers: vec![vec![]],
+            };
+        }
+
+        let mut layers = vec![leaves];
+        while layers.last().expect("layers never empty").len() > 1 {
+            let prev = layers.last().expect("layers never empty");
+            let mut next = Vec::with_capacity(prev.len().div_ceil(2));
+            let mut i = 0;
+            while i < prev.len() {
+                if i + 1 < prev.len() {
+                    next.push(hash_pair(prev[i], prev[i + 1]));
+                    i += 2;
+                } else {
+                    // Odd node out: promote unchanged rather than
+                    // duplicating it, so a lone leaf never gets "paired
+                    // with itself" in the root.
+                    next.push(prev[i]);
+                    i += 1;
+                }
+            }
+            layers.push(next);
+        }
+
+        let root = layers.last().expect("layers never empty")[0];
+        Self { root, layers }
+    }
+
+    /// Inclusion path (sibling hashes, leaf to root) for the leaf at
+    /// `index` in the tree's leaf layer.
+    pub fn proof(&self, mut index: usize) -> Vec<[u8; 32]> {
+        let mut path = Vec::new();
+
+        for layer in &self.layers[..self.layers.len().saturating_sub(1)] {
+            let has_sibling = if index % 2 == 0 {
+                index + 1 < layer.len()
+            } else {
+                true
+            };
+            if has_sibling {
+                let sibling = if index % 2 == 0 { index + 1 } else { index - 1 };
+                path.push(layer[sibling]);
+            }
+            index /= 2;
+        }
+
+        path
+    }
+}
+
+fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    let mut buf = Vec::with_capacity(64);
+    buf.extend_from_slice(&lo);
+    buf.extend_from_slice(&hi);
+    keccak256(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(n: u8) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes[31] = n;
+        keccak256(bytes)
+    }
+
+    #[test]
+    fn test_empty_tree_has_zero_root() {
+        let tree = MerkleTree::build(vec![]);
+        assert_eq!(tree.root, [0u8; 32]);
+    }
+
+    #[test]
+    fn test_single_leaf_is_its_own_root() {
+        let leaves = vec![leaf(1)];
+        let tree = MerkleTree::build(leaves.clone());
+        assert_eq!(tree.root, leaves[0]);
+        assert!(tree.proof(0).is_empty());
+    }
+
+    #[test]
+    fn test_pairing_is_order_independent() {
+        assert_eq!(hash_pair(leaf(1), leaf(2)), hash_pair(leaf(2), leaf(1)));
+    }
+
+    #[test]
+    fn test_inclusion_proof_recomputes_root() {
+        let leaves = vec![leaf(1), leaf(2), leaf(3), leaf(4), leaf(5)];
+        let tree = MerkleTree::build(leaves.clone());
+
+        for (i, &l) in leaves.iter().enumerate() {
+            let path = tree.proof(i);
+            let mut computed = l;
+            let mut idx = i;
+            for sibling in path {
+                computed = hash_pair(computed, sibling);
+                idx /= 2;
+            }
+            let _ = idx;
+            assert_eq!(computed, tree.root);
+        }
+    }
+}
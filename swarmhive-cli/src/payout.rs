@@ -0,0 +1,118 @@
+//! Deterministic epoch payout engine
+//!
+//! 25% of an epoch's total volume goes to the hive, the remaining 75% is
+//! split across miners weighted by `compute_seconds`. Everything is integer
+//! arithmetic; the largest-remainder method distributes the division
+//! leftover so the miner payouts plus the hive cut sum to exactly
+//! `total_volume`, with no rounding drift.
+
+use std::collections::BTreeMap;
+
+const HIVE_SHARE_PERCENT: u128 = 25;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinerPayout {
+    pub miner: String,
+    pub compute_seconds: u64,
+    pub amount: u128,
+}
+
+/// Split `total_volume` into the hive's cut and each miner's payout,
+/// weighted by `compute_seconds_by_miner`. Returns `(hive_amount, payouts)`
+/// sorted by miner ENS for deterministic output.
+pub fn compute_payouts(
+    total_volume: u128,
+    compute_seconds_by_miner: &BTreeMap<String, u64>,
+) -> (u128, Vec<MinerPayout>) {
+    let hive_amount = total_volume * HIVE_SHARE_PERCENT / 100;
+    let miner_pool = total_volume - hive_amount;
+    let total_compute: u128 = compute_seconds_by_miner.values().map(|&s| s as u128).sum();
+
+    // No miner to weight the 75% against: the hive keeps the whole volume
+    // so the split still sums to `total_volume`.
+    if total_compute == 0 {
+        return (total_volume, vec![]);
+    }
+
+    struct Share {
+        miner: String,
+        compute_seconds: u64,
+        base: u128,
+        remainder: u128,
+    }
+
+    let mut shares: Vec<Share> = compute_seconds_by_miner
+        .iter()
+        .map(|(miner, &compute_seconds)| {
+            let numerator = miner_pool * compute_seconds as u128;
+            Share {
+                miner: miner.clone(),
+                compute_seconds,
+                base: numerator / total_compute,
+                remainder: numerator % total_compute,
+            }
+        })
+        .collect();
+
+    let distributed: u128 = shares.iter().map(|s| s.base).sum();
+    let mut leftover = miner_pool - distributed;
+
+    // Largest remainder first; ties broken by miner ENS so the split is
+    // reproducible across runs.
+    shares.sort_by(|a, b| b.remainder.cmp(&a.remainder).then_with(|| a.miner.cmp(&b.miner)));
+
+    let mut payouts: Vec<MinerPayout> = shares
+        .into_iter()
+        .map(|s| MinerPayout {
+            miner: s.miner,
+            compute_seconds: s.compute_seconds,
+            amount: s.base,
+        })
+        .collect();
+
+    let mut i = 0;
+    while leftover > 0 {
+        payouts[i % payouts.len()].amount += 1;
+        leftover -= 1;
+        i += 1;
+    }
+
+    payouts.sort_by(|a, b| a.miner.cmp(&b.miner));
+    (hive_amount, payouts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_epoch_sends_everything_to_hive() {
+        let (hive, payouts) = compute_payouts(1000, &BTreeMap::new());
+        assert_eq!(hive, 1000);
+        assert!(payouts.is_empty());
+    }
+
+    #[test]
+    fn test_split_sums_exactly_with_no_rounding_drift() {
+        let mut compute = BTreeMap::new();
+        compute.insert("miner.alice.eth".to_string(), 10);
+        compute.insert("miner.bob.eth".to_string(), 7);
+        compute.insert("miner.carol.eth".to_string(), 3);
+
+        let (hive, payouts) = compute_payouts(100, &compute);
+        let total: u128 = hive + payouts.iter().map(|p| p.amount).sum::<u128>();
+        assert_eq!(total, 100);
+    }
+
+    #[test]
+    fn test_largest_remainder_breaks_ties_deterministically() {
+        let mut compute = BTreeMap::new();
+        compute.insert("miner.a.eth".to_string(), 1);
+        compute.insert("miner.b.eth".to_string(), 1);
+        compute.insert("miner.c.eth".to_string(), 1);
+
+        let (_, payouts) = compute_payouts(10, &compute);
+        let (_, payouts_again) = compute_payouts(10, &compute);
+        assert_eq!(payouts, payouts_again);
+    }
+}
@@ -0,0 +1,122 @@
+//! On-chain anchoring of epoch seals
+//!
+//! Records `(epoch, merkle_root, seal_cid)` against a `ChainConfig.contract_address`
+//! via `anchorSeal(uint256,bytes32,string)`, so epoch payouts are verifiable
+//! against an on-chain commitment instead of only the off-chain `EPOCH_SEAL`
+//! snapshot in IPFS.
+
+use crate::config::ChainConfig;
+use anyhow::{Context, Result};
+use ethers::abi::Token;
+use ethers::middleware::SignerMiddleware;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Address, Bytes, Eip1559TransactionRequest, TransactionReceipt};
+use ethers::utils::keccak256;
+use std::sync::Arc;
+
+/// Outcome of `Settlement::anchor_seal`: either a broadcast receipt or, for
+/// `--dry-run`, a preview of the transaction that would have been sent.
+pub enum AnchorOutcome {
+    Preview {
+        to: Address,
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+        calldata: Bytes,
+    },
+    Sent(Box<TransactionReceipt>),
+}
+
+/// Anchors epoch seals to an Ethereum (or L2) contract via an ethers
+/// provider/middleware, signing with the controller wallet loaded from
+/// `identity.key_path`.
+pub struct Settlement {
+    middleware: Arc<SignerMiddleware<Provider<Http>, LocalWallet>>,
+    contract_address: Address,
+}
+
+impl Settlement {
+    pub async fn new(chain: &ChainConfig, wallet: LocalWallet) -> Result<Self> {
+        let provider = Provider::<Http>::try_from(chain.rpc_url.as_str())
+            .context("invalid chain.rpc_url")?;
+        let wallet = wallet.with_chain_id(chain.chain_id);
+        let contract_address = chain
+            .contract_address
+            .parse::<Address>()
+            .context("invalid chain.contract_address")?;
+
+        Ok(Self {
+            middleware: Arc::new(SignerMiddleware::new(provider, wallet)),
+            contract_address,
+        })
+    }
+
+    /// Anchor `(epoch, merkle_root, seal_cid)` on-chain. With `dry_run` set,
+    /// estimates fees and the access list but does not broadcast.
+    pub async fn anchor_seal(
+        &self,
+        epoch: u64,
+        merkle_root: [u8; 32],
+        seal_cid: &str,
+        dry_run: bool,
+    ) -> Result<AnchorOutcome> {
+        let calldata = encode_anchor_calldata(epoch, merkle_root, seal_cid);
+
+        let mut tx = Eip1559TransactionRequest::new()
+            .to(self.contract_address)
+            .data(calldata.clone());
+
+        let (max_fee_per_gas, max_priority_fee_per_gas) = self
+            .middleware
+            .estimate_eip1559_fees(None)
+            .await
+            .context("failed to estimate EIP-1559 fees")?;
+        tx = tx
+            .max_fee_per_gas(max_fee_per_gas)
+            .max_priority_fee_per_gas(max_priority_fee_per_gas);
+
+        let typed_tx = tx.clone().into();
+        let access_list = self
+            .middleware
+            .create_access_list(&typed_tx, None)
+            .await
+            .context("failed to build EIP-2930 access list")?
+            .access_list;
+        tx = tx.access_list(access_list);
+
+        if dry_run {
+            return Ok(AnchorOutcome::Preview {
+                to: self.contract_address,
+                max_fee_per_gas: max_fee_per_gas.as_u128(),
+                max_priority_fee_per_gas: max_priority_fee_per_gas.as_u128(),
+                calldata,
+            });
+        }
+
+        let pending = self
+            .middleware
+            .send_transaction(tx, None)
+            .await
+            .context("failed to broadcast anchor transaction")?;
+        let receipt = pending
+            .await
+            .context("anchor transaction receipt unavailable")?
+            .context("anchor transaction dropped from mempool")?;
+
+        Ok(AnchorOutcome::Sent(Box::new(receipt)))
+    }
+}
+
+/// ABI-encode a call to `anchorSeal(uint256 epoch, bytes32 merkleRoot, string sealCid)`.
+fn encode_anchor_calldata(epoch: u64, merkle_root: [u8; 32], seal_cid: &str) -> Bytes {
+    let selector = &keccak256(b"anchorSeal(uint256,bytes32,string)")[..4];
+    let tokens = vec![
+        Token::Uint(epoch.into()),
+        Token::FixedBytes(merkle_root.to_vec()),
+        Token::String(seal_cid.to_string()),
+    ];
+
+    let mut data = selector.to_vec();
+    data.extend(ethers::abi::encode(&tokens));
+    Bytes::from(data)
+}